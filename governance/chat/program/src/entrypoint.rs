@@ -0,0 +1,6 @@
+//! Program entrypoint
+
+use crate::processor::process_instruction;
+use gemachain_program::entrypoint;
+
+entrypoint!(process_instruction);
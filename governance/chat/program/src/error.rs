@@ -0,0 +1,79 @@
+//! Error types
+
+use gemachain_program::{decode_error::DecodeError, program_error::ProgramError};
+use num_derive::FromPrimitive;
+use thiserror::Error;
+
+/// Errors that may be returned by the GovernanceChat program
+#[derive(Clone, Debug, Eq, Error, FromPrimitive, PartialEq)]
+pub enum GovernanceChatError {
+    /// Token owner doesn't have enough tokens to comment on Proposal
+    #[error("Not enough tokens to comment on Proposal")]
+    NotEnoughTokensToCommentProposal,
+
+    /// Voter weight record account does not match the given Realm
+    #[error("Invalid VoterWeightRecord for Realm")]
+    InvalidVoterWeightRecordForRealm,
+
+    /// Voter weight record account does not match the commenter's TokenOwnerRecord
+    #[error("Invalid VoterWeightRecord owner")]
+    InvalidVoterWeightRecordForTokenOwner,
+
+    /// Voter weight record has expired and can no longer be used
+    #[error("VoterWeightRecord has expired")]
+    VoterWeightRecordExpired,
+
+    /// Voter weight record was not issued for commenting
+    #[error("Invalid VoterWeightAction for CommentProposal")]
+    InvalidVoterWeightAction,
+
+    /// Proposal is executing or in a terminal state and no longer accepts comments
+    #[error("Proposal is not in a commentable state")]
+    ProposalNotInCommentableState,
+
+    /// The provided ReplyTo account does not match the given address or is not owned
+    /// by the GovernanceChat program, or (for a reaction) does not reply to a Text message
+    #[error("Invalid ReplyTo account")]
+    InvalidReplyTo,
+
+    /// A Reaction message must reply to an existing message
+    #[error("ReplyTo is required for a Reaction message")]
+    ReplyToRequiredForReaction,
+
+    /// The provided SignatoryRecord does not belong to the Proposal or to the message author
+    #[error("Invalid SignatoryRecord for Proposal")]
+    InvalidSignatoryRecordForProposal,
+
+    /// The provided Governance account does not match the Proposal's Governance
+    #[error("Invalid Governance for Proposal")]
+    InvalidGovernanceForProposal,
+
+    /// The Realm's voter-weight addin is configured but no VoterWeightRecord was provided
+    #[error("VoterWeightRecord is required for commenting")]
+    VoterWeightRecordRequired,
+}
+
+impl From<GovernanceChatError> for ProgramError {
+    fn from(e: GovernanceChatError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+impl<T> DecodeError<T> for GovernanceChatError {
+    fn type_of() -> &'static str {
+        "GovernanceChat Error"
+    }
+}
+
+impl gemachain_program::program_error::PrintProgramError for GovernanceChatError {
+    fn print<E>(&self)
+    where
+        E: 'static
+            + std::error::Error
+            + DecodeError<E>
+            + PrintProgramError
+            + num_traits::FromPrimitive,
+    {
+        gemachain_program::msg!("GOVERNANCE-CHAT-ERROR: {}", &self.to_string());
+    }
+}
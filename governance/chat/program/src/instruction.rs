@@ -0,0 +1,103 @@
+//! Program instructions
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use gemachain_program::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    rent, system_program, sysvar,
+};
+
+use crate::state::{get_reaction_summary_address, MessageBody};
+
+/// Instructions supported by the GovernanceChat program
+#[derive(Clone, Debug, PartialEq, BorshDeserialize, BorshSerialize)]
+pub enum GovernanceChatInstruction {
+    /// Posts a message (comment) for a given Proposal
+    ///
+    /// 0. `[]` Governance program the Proposal belongs to
+    /// 1. `[]` Governance account
+    /// 2. `[]` Proposal account
+    /// 3. `[]` TokenOwnerRecord account of the message author
+    /// 4. `[signer]` Governance Authority (token owner or its delegate)
+    /// 5. `[writable, signer]` ChatMessage account - must be a new keypair
+    /// 6. `[writable, signer]` Payer
+    /// 7. `[]` System program
+    /// 8. `[]` Rent sysvar
+    /// 9. `[]` Clock sysvar
+    /// 10. `[]` ReplyTo ChatMessage account (required when `reply_to` is Some)
+    /// 11. `[writable]` ReactionSummary account for the ReplyTo message (required when
+    ///     `body` is `MessageBody::Reaction`)
+    /// 12. `[]` SignatoryRecord account for the author, or the System program id as a
+    ///     placeholder when the Proposal is not in the SigningOff state
+    /// 13. `[]` VoterWeightRecord account, or the System program id as a placeholder
+    ///     when the Realm has no voter-weight addin configured for the message
+    ///     author's mint
+    PostMessage {
+        /// Message body
+        body: MessageBody,
+
+        /// Message this message replies to, if any
+        reply_to: Option<Pubkey>,
+    },
+}
+
+/// Creates PostMessage instruction
+#[allow(clippy::too_many_arguments)]
+pub fn post_message(
+    program_id: &Pubkey,
+    governance_program_id: &Pubkey,
+    governance: &Pubkey,
+    proposal: &Pubkey,
+    token_owner_record: &Pubkey,
+    governance_authority: &Pubkey,
+    reply_to: Option<Pubkey>,
+    chat_message: &Pubkey,
+    payer: &Pubkey,
+    body: MessageBody,
+    signatory_record: Option<Pubkey>,
+    voter_weight_record: Option<Pubkey>,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*governance_program_id, false),
+        AccountMeta::new_readonly(*governance, false),
+        AccountMeta::new_readonly(*proposal, false),
+        AccountMeta::new_readonly(*token_owner_record, false),
+        AccountMeta::new_readonly(*governance_authority, true),
+        AccountMeta::new(*chat_message, true),
+        AccountMeta::new(*payer, true),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(rent::id(), false),
+        AccountMeta::new_readonly(sysvar::clock::id(), false),
+    ];
+
+    if let Some(reply_to) = reply_to {
+        accounts.push(AccountMeta::new_readonly(reply_to, false));
+
+        if matches!(body, MessageBody::Reaction(_)) {
+            let reaction_summary = get_reaction_summary_address(program_id, &reply_to);
+            accounts.push(AccountMeta::new(reaction_summary, false));
+        }
+    }
+
+    // SignatoryRecord and VoterWeightRecord are always two fixed trailing slots so the
+    // processor never has to infer which optional account it was handed from the
+    // Proposal's runtime state. Callers that don't have one pass the System program id,
+    // which can never be a valid SignatoryRecord or VoterWeightRecord (both are owned by
+    // other programs), as an explicit "not provided" placeholder.
+    accounts.push(AccountMeta::new_readonly(
+        signatory_record.unwrap_or_else(system_program::id),
+        false,
+    ));
+    accounts.push(AccountMeta::new_readonly(
+        voter_weight_record.unwrap_or_else(system_program::id),
+        false,
+    ));
+
+    let instruction = GovernanceChatInstruction::PostMessage { body, reply_to };
+
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data: instruction.try_to_vec().unwrap(),
+    }
+}
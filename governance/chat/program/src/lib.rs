@@ -0,0 +1,13 @@
+#![deny(missing_docs)]
+
+//! A program for commenting on spl-governance Proposals
+
+pub mod error;
+pub mod instruction;
+pub mod processor;
+pub mod state;
+
+#[cfg(not(feature = "no-entrypoint"))]
+pub mod entrypoint;
+
+gemachain_program::declare_id!("GovernanceChat11111111111111111111111111111");
@@ -0,0 +1,400 @@
+//! Program processor
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use gemachain_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction, system_program,
+    sysvar::Sysvar,
+};
+use spl_governance::state::{
+    enums::ProposalState,
+    governance::get_governance_data,
+    proposal::{get_proposal_data, ProposalV2},
+    realm::get_realm_data,
+    signatory_record::get_signatory_record_data,
+    token_owner_record::get_token_owner_record_data_for_realm,
+};
+use spl_governance_addin_api::voter_weight::{VoterWeightAction, VoterWeightRecord};
+
+use crate::{
+    error::GovernanceChatError,
+    instruction::GovernanceChatInstruction,
+    state::{
+        get_reaction_summary_address_seeds, ChatMessage, GovernanceChatAccountType, MessageBody,
+        ReactionCount, ReactionSummary,
+    },
+};
+
+/// Processes an instruction
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    input: &[u8],
+) -> ProgramResult {
+    let instruction = GovernanceChatInstruction::try_from_slice(input)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    match instruction {
+        GovernanceChatInstruction::PostMessage { body, reply_to } => {
+            process_post_message(program_id, accounts, body, reply_to)
+        }
+    }
+}
+
+fn process_post_message(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    body: MessageBody,
+    reply_to: Option<Pubkey>,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let governance_program_info = next_account_info(account_info_iter)?;
+    let governance_info = next_account_info(account_info_iter)?;
+    let proposal_info = next_account_info(account_info_iter)?;
+    let token_owner_record_info = next_account_info(account_info_iter)?;
+    let governance_authority_info = next_account_info(account_info_iter)?;
+    let chat_message_info = next_account_info(account_info_iter)?;
+    let payer_info = next_account_info(account_info_iter)?;
+    let system_info = next_account_info(account_info_iter)?;
+    let _rent_info = next_account_info(account_info_iter)?;
+    let clock_info = next_account_info(account_info_iter)?;
+
+    let proposal_data = get_proposal_data(governance_program_info.key, proposal_info)?;
+
+    if governance_info.key != &proposal_data.governance {
+        return Err(GovernanceChatError::InvalidGovernanceForProposal.into());
+    }
+
+    assert_proposal_accepts_comments(&proposal_data)?;
+
+    // The commenter's TokenOwnerRecord may belong to either the community or the council mint
+    // of the Proposal's Realm, regardless of which mint the Proposal itself was created with.
+    let token_owner_record_data = get_token_owner_record_data_for_realm(
+        governance_program_info.key,
+        token_owner_record_info,
+        &proposal_data.realm,
+    )?;
+
+    token_owner_record_data.assert_token_owner_or_delegate_is_signer(governance_authority_info)?;
+
+    // Reactions must target an existing Text message; reaction counts for that message are
+    // tallied in its ReactionSummary PDA.
+    if let Some(reply_to) = reply_to {
+        let reply_to_info = next_account_info(account_info_iter)?;
+
+        if reply_to_info.key != &reply_to || reply_to_info.owner != program_id {
+            return Err(GovernanceChatError::InvalidReplyTo.into());
+        }
+
+        let reply_to_message = ChatMessage::try_from_slice(&reply_to_info.data.borrow())?;
+
+        if let MessageBody::Reaction(reaction) = &body {
+            if !matches!(reply_to_message.body, MessageBody::Text(_)) {
+                return Err(GovernanceChatError::InvalidReplyTo.into());
+            }
+
+            let reaction_summary_info = next_account_info(account_info_iter)?;
+            update_reaction_summary(
+                program_id,
+                &reply_to,
+                reaction,
+                reaction_summary_info,
+                payer_info,
+                system_info,
+            )?;
+        }
+    } else if matches!(body, MessageBody::Reaction(_)) {
+        return Err(GovernanceChatError::ReplyToRequiredForReaction.into());
+    }
+
+    // SignatoryRecord and VoterWeightRecord are always two fixed trailing accounts (the
+    // System program id standing in for "not provided"), regardless of the Proposal's
+    // state or whether the caller supplied either one. This keeps account consumption
+    // independent of the caller's `Option`s agreeing with the Proposal's runtime state.
+    let signatory_record_info = next_account_info(account_info_iter)?;
+    let voter_weight_record_info = next_account_info(account_info_iter)?;
+
+    // While a Proposal is being signed off, commenting is restricted to its signatories so
+    // reviewers can discuss privately before the Proposal opens up to the wider token holders.
+    // Once it reaches Voting (or beyond) normal token-weight gating takes back over.
+    if proposal_data.state == ProposalState::SigningOff {
+        if signatory_record_info.key == &system_program::id() {
+            return Err(GovernanceChatError::InvalidSignatoryRecordForProposal.into());
+        }
+
+        let signatory_record_data =
+            get_signatory_record_data(governance_program_info.key, signatory_record_info)?;
+
+        if signatory_record_data.proposal != *proposal_info.key
+            || signatory_record_data.signatory != token_owner_record_data.governing_token_owner
+        {
+            return Err(GovernanceChatError::InvalidSignatoryRecordForProposal.into());
+        }
+    } else {
+        let realm_data = get_realm_data(governance_program_info.key, &proposal_data.realm)?;
+
+        let is_council_mint =
+            realm_data.config.council_mint == Some(token_owner_record_data.governing_token_mint);
+
+        let voter_weight_addin = if is_council_mint {
+            realm_data.config.council_voter_weight_addin
+        } else {
+            realm_data.config.community_voter_weight_addin
+        };
+
+        let voter_weight = if let Some(voter_weight_addin) = voter_weight_addin {
+            if voter_weight_record_info.key == &system_program::id() {
+                return Err(GovernanceChatError::VoterWeightRecordRequired.into());
+            }
+
+            let voter_weight_record_data = get_voter_weight_record_data_for_commenting(
+                &voter_weight_addin,
+                voter_weight_record_info,
+                &proposal_data.realm,
+                &token_owner_record_data.governing_token_mint,
+                &token_owner_record_data.governing_token_owner,
+                proposal_info.key,
+                clock_info,
+            )?;
+
+            voter_weight_record_data.voter_weight
+        } else {
+            token_owner_record_data.governing_token_deposit_amount
+        };
+
+        // Commenting reuses the Governance's own proposal-creation thresholds as its per-mint
+        // minimum: a token owner who isn't yet allowed to create a Proposal for this Governance
+        // shouldn't be able to comment on one either, and the two mints can genuinely require
+        // different amounts.
+        let governance_data = get_governance_data(governance_program_info.key, governance_info)?;
+
+        let min_tokens_to_comment = if is_council_mint {
+            governance_data.config.min_council_tokens_to_create_proposal
+        } else {
+            governance_data
+                .config
+                .min_community_tokens_to_create_proposal
+        };
+
+        if voter_weight < min_tokens_to_comment {
+            return Err(GovernanceChatError::NotEnoughTokensToCommentProposal.into());
+        }
+    }
+
+    let clock = Clock::from_account_info(clock_info)?;
+
+    let chat_message_data = ChatMessage {
+        account_type: GovernanceChatAccountType::ChatMessage,
+        proposal: *proposal_info.key,
+        author: token_owner_record_data.governing_token_owner,
+        posted_at: clock.unix_timestamp,
+        reply_to,
+        body,
+    };
+
+    create_and_serialize_account(
+        payer_info,
+        chat_message_info,
+        &chat_message_data,
+        system_info,
+    )
+}
+
+fn get_voter_weight_record_data_for_commenting(
+    voter_weight_addin: &Pubkey,
+    voter_weight_record_info: &AccountInfo,
+    realm: &Pubkey,
+    governing_token_mint: &Pubkey,
+    governing_token_owner: &Pubkey,
+    proposal: &Pubkey,
+    clock_info: &AccountInfo,
+) -> Result<VoterWeightRecord, ProgramError> {
+    if voter_weight_record_info.owner != voter_weight_addin {
+        return Err(GovernanceChatError::InvalidVoterWeightRecordForRealm.into());
+    }
+
+    let voter_weight_record_data =
+        VoterWeightRecord::try_from_slice(&voter_weight_record_info.data.borrow())?;
+
+    if voter_weight_record_data.realm != *realm
+        || voter_weight_record_data.governing_token_mint != *governing_token_mint
+    {
+        return Err(GovernanceChatError::InvalidVoterWeightRecordForRealm.into());
+    }
+
+    if voter_weight_record_data.governing_token_owner != *governing_token_owner {
+        return Err(GovernanceChatError::InvalidVoterWeightRecordForTokenOwner.into());
+    }
+
+    let clock = Clock::from_account_info(clock_info)?;
+
+    if let Some(voter_weight_expiry) = voter_weight_record_data.voter_weight_expiry {
+        if voter_weight_expiry < clock.slot {
+            return Err(GovernanceChatError::VoterWeightRecordExpired.into());
+        }
+    }
+
+    match voter_weight_record_data.weight_action {
+        Some(VoterWeightAction::CommentProposal) => {}
+        _ => return Err(GovernanceChatError::InvalidVoterWeightAction.into()),
+    }
+
+    if let Some(weight_action_target) = voter_weight_record_data.weight_action_target {
+        if weight_action_target != *proposal {
+            return Err(GovernanceChatError::InvalidVoterWeightAction.into());
+        }
+    }
+
+    Ok(voter_weight_record_data)
+}
+
+/// Comments are only accepted while a Proposal is still being discussed, voted on, or
+/// awaiting execution of a passed vote. `Succeeded` is deliberately commentable even though
+/// voting has ended: the Proposal hasn't been executed yet, so discussion is still live.
+/// Once execution starts (`Executing`, `ExecutingWithErrors`) or the Proposal reaches a
+/// terminal state, the chat history is frozen.
+fn assert_proposal_accepts_comments(proposal_data: &ProposalV2) -> Result<(), ProgramError> {
+    match proposal_data.state {
+        ProposalState::Cancelled
+        | ProposalState::Defeated
+        | ProposalState::Completed
+        | ProposalState::Executing
+        | ProposalState::ExecutingWithErrors => {
+            Err(GovernanceChatError::ProposalNotInCommentableState.into())
+        }
+        ProposalState::Draft
+        | ProposalState::SigningOff
+        | ProposalState::Voting
+        | ProposalState::Succeeded => Ok(()),
+    }
+}
+
+/// Creates the ReactionSummary PDA for `message` on first use and bumps the count for `reaction`
+fn update_reaction_summary(
+    program_id: &Pubkey,
+    message: &Pubkey,
+    reaction: &str,
+    reaction_summary_info: &AccountInfo,
+    payer_info: &AccountInfo,
+    system_info: &AccountInfo,
+) -> ProgramResult {
+    let seeds = get_reaction_summary_address_seeds(message);
+    let (reaction_summary_address, bump_seed) = Pubkey::find_program_address(&seeds, program_id);
+
+    if reaction_summary_address != *reaction_summary_info.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let mut reaction_summary_data = if reaction_summary_info.data_is_empty() {
+        ReactionSummary {
+            account_type: GovernanceChatAccountType::ReactionSummary,
+            message: *message,
+            reactions: vec![],
+        }
+    } else {
+        ReactionSummary::try_from_slice(&reaction_summary_info.data.borrow())?
+    };
+
+    match reaction_summary_data
+        .reactions
+        .iter_mut()
+        .find(|r| r.reaction == reaction)
+    {
+        Some(reaction_count) => reaction_count.count += 1,
+        None => reaction_summary_data.reactions.push(ReactionCount {
+            reaction: reaction.to_string(),
+            count: 1,
+        }),
+    }
+
+    let serialized_data = reaction_summary_data.try_to_vec()?;
+
+    if reaction_summary_info.data_is_empty() {
+        let rent = Rent::get()?;
+        let create_account_instruction = system_instruction::create_account(
+            payer_info.key,
+            reaction_summary_info.key,
+            rent.minimum_balance(serialized_data.len()),
+            serialized_data.len() as u64,
+            program_id,
+        );
+
+        let message_seed: &[u8] = message.as_ref();
+        invoke_signed(
+            &create_account_instruction,
+            &[
+                payer_info.clone(),
+                reaction_summary_info.clone(),
+                system_info.clone(),
+            ],
+            &[&[b"reaction-summary", message_seed, &[bump_seed]]],
+        )?;
+    } else if reaction_summary_info.data_len() < serialized_data.len() {
+        reaction_summary_info.realloc(serialized_data.len(), false)?;
+
+        let rent = Rent::get()?;
+        let minimum_balance = rent.minimum_balance(serialized_data.len());
+        let additional_rent = minimum_balance.saturating_sub(reaction_summary_info.lamports());
+
+        if additional_rent > 0 {
+            invoke(
+                &system_instruction::transfer(
+                    payer_info.key,
+                    reaction_summary_info.key,
+                    additional_rent,
+                ),
+                &[
+                    payer_info.clone(),
+                    reaction_summary_info.clone(),
+                    system_info.clone(),
+                ],
+            )?;
+        }
+    }
+
+    reaction_summary_info.data.borrow_mut()[..serialized_data.len()]
+        .copy_from_slice(&serialized_data);
+
+    Ok(())
+}
+
+fn create_and_serialize_account(
+    payer_info: &AccountInfo,
+    account_info: &AccountInfo,
+    account_data: &ChatMessage,
+    system_info: &AccountInfo,
+) -> ProgramResult {
+    let serialized_data = account_data.try_to_vec()?;
+
+    let rent = Rent::get()?;
+    let create_account_instruction = system_instruction::create_account(
+        payer_info.key,
+        account_info.key,
+        rent.minimum_balance(serialized_data.len()),
+        serialized_data.len() as u64,
+        &crate::id(),
+    );
+
+    invoke(
+        &create_account_instruction,
+        &[
+            payer_info.clone(),
+            account_info.clone(),
+            system_info.clone(),
+        ],
+    )?;
+
+    account_info
+        .data
+        .borrow_mut()
+        .copy_from_slice(&serialized_data);
+
+    Ok(())
+}
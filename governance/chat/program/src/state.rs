@@ -0,0 +1,85 @@
+//! Program accounts
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use gemachain_program::pubkey::Pubkey;
+
+/// Defines all GovernanceChat accounts types
+#[derive(Clone, Debug, PartialEq, BorshDeserialize, BorshSerialize)]
+pub enum GovernanceChatAccountType {
+    /// Default uninitialized account state
+    Uninitialized,
+
+    /// Chat message
+    ChatMessage,
+
+    /// Aggregated reaction counts for a ChatMessage
+    ReactionSummary,
+}
+
+/// Body of a chat message
+#[derive(Clone, Debug, PartialEq, BorshDeserialize, BorshSerialize)]
+pub enum MessageBody {
+    /// Text message
+    Text(String),
+
+    /// Reaction to another message, given as an emoji (or short code)
+    Reaction(String),
+}
+
+/// Chat message
+#[derive(Clone, Debug, PartialEq, BorshDeserialize, BorshSerialize)]
+pub struct ChatMessage {
+    /// Account type
+    pub account_type: GovernanceChatAccountType,
+
+    /// Proposal the message is for
+    pub proposal: Pubkey,
+
+    /// Author of the message
+    pub author: Pubkey,
+
+    /// Timestamp at which the message was posted
+    pub posted_at: i64,
+
+    /// Parent message this message replies to, if any
+    pub reply_to: Option<Pubkey>,
+
+    /// Message body
+    pub body: MessageBody,
+}
+
+/// Tally of a single reaction string posted against a message
+#[derive(Clone, Debug, PartialEq, BorshDeserialize, BorshSerialize)]
+pub struct ReactionCount {
+    /// The reaction, for example an emoji or a short code
+    pub reaction: String,
+
+    /// Number of times the reaction was posted against the message
+    pub count: u64,
+}
+
+/// Aggregated reaction counts for a single ChatMessage
+///
+/// Lets clients render reaction totals for a message without scanning every
+/// ChatMessage account that replies to it.
+#[derive(Clone, Debug, PartialEq, BorshDeserialize, BorshSerialize)]
+pub struct ReactionSummary {
+    /// Account type
+    pub account_type: GovernanceChatAccountType,
+
+    /// The message the reactions were posted against
+    pub message: Pubkey,
+
+    /// Reaction counts, one entry per distinct reaction string
+    pub reactions: Vec<ReactionCount>,
+}
+
+/// Returns ReactionSummary PDA seeds
+pub fn get_reaction_summary_address_seeds(message: &Pubkey) -> [&[u8]; 2] {
+    [b"reaction-summary", message.as_ref()]
+}
+
+/// Returns ReactionSummary PDA address
+pub fn get_reaction_summary_address(program_id: &Pubkey, message: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&get_reaction_summary_address_seeds(message), program_id).0
+}
@@ -0,0 +1,507 @@
+mod program_test;
+
+use gemachain_program::pubkey::Pubkey;
+use gemachain_sdk::signer::Signer;
+use program_test::{clone_keypair, GovernanceChatProgramTest};
+use spl_governance_chat::{error::GovernanceChatError, state::MessageBody};
+
+#[tokio::test]
+async fn test_post_message_to_draft_proposal() {
+    // Arrange
+    let mut governance_chat_test = GovernanceChatProgramTest::start_new().await;
+    let proposal_cookie = governance_chat_test.with_proposal().await;
+
+    let message_body = MessageBody::Text("Hello".to_string());
+
+    // Act
+    let chat_message_cookie = governance_chat_test
+        .with_chat_message(&proposal_cookie, None, message_body, None, None)
+        .await
+        .unwrap();
+
+    // Assert
+    let chat_message_account = governance_chat_test
+        .get_message_account(&chat_message_cookie.address)
+        .await;
+
+    assert_eq!(chat_message_account.proposal, proposal_cookie.address);
+    assert_eq!(
+        chat_message_account.author,
+        proposal_cookie.token_owner.pubkey()
+    );
+}
+
+#[tokio::test]
+async fn test_post_message_to_cancelled_proposal_errors() {
+    // Arrange
+    let mut governance_chat_test = GovernanceChatProgramTest::start_new().await;
+    let proposal_cookie = governance_chat_test.with_proposal().await;
+    let proposal_cookie = governance_chat_test.cancel_proposal(proposal_cookie).await;
+
+    let message_body = MessageBody::Text("Too late".to_string());
+
+    // Act
+    let err = governance_chat_test
+        .with_chat_message(&proposal_cookie, None, message_body, None, None)
+        .await
+        .err()
+        .unwrap();
+
+    // Assert
+    assert_eq!(
+        err,
+        GovernanceChatError::ProposalNotInCommentableState.into()
+    );
+}
+
+#[tokio::test]
+async fn test_post_message_to_defeated_proposal_errors() {
+    // Arrange
+    let mut governance_chat_test = GovernanceChatProgramTest::start_new().await;
+    let proposal_cookie = governance_chat_test.with_proposal().await;
+    let signatory = clone_keypair(&proposal_cookie.token_owner);
+    let proposal_cookie = governance_chat_test
+        .sign_off_proposal(proposal_cookie, &signatory)
+        .await;
+    let proposal_cookie = governance_chat_test
+        .cast_yes_no_vote(proposal_cookie, false)
+        .await;
+    let proposal_cookie = governance_chat_test.finalize_vote(proposal_cookie).await;
+
+    let message_body = MessageBody::Text("Still here?".to_string());
+
+    // Act
+    let err = governance_chat_test
+        .with_chat_message(&proposal_cookie, None, message_body, None, None)
+        .await
+        .err()
+        .unwrap();
+
+    // Assert
+    assert_eq!(
+        err,
+        GovernanceChatError::ProposalNotInCommentableState.into()
+    );
+}
+
+#[tokio::test]
+async fn test_post_message_to_succeeded_proposal() {
+    // Arrange
+    //
+    // A passed vote hasn't been executed yet, so the Proposal should still accept comments.
+    let mut governance_chat_test = GovernanceChatProgramTest::start_new().await;
+    let proposal_cookie = governance_chat_test.with_proposal().await;
+    let signatory = clone_keypair(&proposal_cookie.token_owner);
+    let proposal_cookie = governance_chat_test
+        .sign_off_proposal(proposal_cookie, &signatory)
+        .await;
+    let proposal_cookie = governance_chat_test
+        .cast_yes_no_vote(proposal_cookie, true)
+        .await;
+    let proposal_cookie = governance_chat_test.finalize_vote(proposal_cookie).await;
+
+    let message_body = MessageBody::Text("Great proposal".to_string());
+
+    // Act
+    let chat_message_cookie = governance_chat_test
+        .with_chat_message(&proposal_cookie, None, message_body, None, None)
+        .await
+        .unwrap();
+
+    // Assert
+    let chat_message_account = governance_chat_test
+        .get_message_account(&chat_message_cookie.address)
+        .await;
+    assert_eq!(chat_message_account.proposal, proposal_cookie.address);
+}
+
+#[tokio::test]
+async fn test_post_reaction_aggregates_counts() {
+    // Arrange
+    let mut governance_chat_test = GovernanceChatProgramTest::start_new().await;
+    let proposal_cookie = governance_chat_test.with_proposal().await;
+
+    let text_message_cookie = governance_chat_test
+        .with_chat_message(
+            &proposal_cookie,
+            None,
+            MessageBody::Text("Hello".to_string()),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+    // Act
+    governance_chat_test
+        .with_chat_message(
+            &proposal_cookie,
+            Some(text_message_cookie.address),
+            MessageBody::Reaction("+1".to_string()),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+    governance_chat_test
+        .with_chat_message(
+            &proposal_cookie,
+            Some(text_message_cookie.address),
+            MessageBody::Reaction("+1".to_string()),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+    // Assert
+    let reaction_summary = governance_chat_test
+        .get_reaction_summary(&text_message_cookie.address)
+        .await;
+
+    assert_eq!(reaction_summary.reactions.len(), 1);
+    assert_eq!(reaction_summary.reactions[0].reaction, "+1");
+    assert_eq!(reaction_summary.reactions[0].count, 2);
+}
+
+#[tokio::test]
+async fn test_post_reaction_to_reaction_errors() {
+    // Arrange
+    let mut governance_chat_test = GovernanceChatProgramTest::start_new().await;
+    let proposal_cookie = governance_chat_test.with_proposal().await;
+
+    let text_message_cookie = governance_chat_test
+        .with_chat_message(
+            &proposal_cookie,
+            None,
+            MessageBody::Text("Hello".to_string()),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+    let reaction_message_cookie = governance_chat_test
+        .with_chat_message(
+            &proposal_cookie,
+            Some(text_message_cookie.address),
+            MessageBody::Reaction("+1".to_string()),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+    // Act
+    let err = governance_chat_test
+        .with_chat_message(
+            &proposal_cookie,
+            Some(reaction_message_cookie.address),
+            MessageBody::Reaction("tada".to_string()),
+            None,
+            None,
+        )
+        .await
+        .err()
+        .unwrap();
+
+    // Assert
+    assert_eq!(err, GovernanceChatError::InvalidReplyTo.into());
+}
+
+#[tokio::test]
+async fn test_post_message_below_community_min_tokens_errors() {
+    // Arrange
+    let mut governance_chat_test = GovernanceChatProgramTest::start_new().await;
+    let proposal_cookie = governance_chat_test.with_proposal().await;
+
+    let plugin_program_id = Pubkey::new_unique();
+    governance_chat_test
+        .with_voter_weight_addin(&proposal_cookie.realm_address, &plugin_program_id)
+        .await;
+
+    let voter_weight_record_cookie = governance_chat_test
+        .with_voter_weight_record(&proposal_cookie, &proposal_cookie.token_owner.pubkey(), 3)
+        .await;
+
+    // Act
+    let err = governance_chat_test
+        .with_chat_message(
+            &proposal_cookie,
+            None,
+            MessageBody::Text("Not enough weight".to_string()),
+            None,
+            Some(voter_weight_record_cookie.address),
+        )
+        .await
+        .err()
+        .unwrap();
+
+    // Assert
+    assert_eq!(
+        err,
+        GovernanceChatError::NotEnoughTokensToCommentProposal.into()
+    );
+}
+
+#[tokio::test]
+async fn test_post_message_at_community_min_tokens_succeeds() {
+    // Arrange
+    let mut governance_chat_test = GovernanceChatProgramTest::start_new().await;
+    let proposal_cookie = governance_chat_test.with_proposal().await;
+
+    let plugin_program_id = Pubkey::new_unique();
+    governance_chat_test
+        .with_voter_weight_addin(&proposal_cookie.realm_address, &plugin_program_id)
+        .await;
+
+    let voter_weight_record_cookie = governance_chat_test
+        .with_voter_weight_record(&proposal_cookie, &proposal_cookie.token_owner.pubkey(), 5)
+        .await;
+
+    // Act
+    let chat_message_cookie = governance_chat_test
+        .with_chat_message(
+            &proposal_cookie,
+            None,
+            MessageBody::Text("Just enough weight".to_string()),
+            None,
+            Some(voter_weight_record_cookie.address),
+        )
+        .await
+        .unwrap();
+
+    // Assert
+    let chat_message_account = governance_chat_test
+        .get_message_account(&chat_message_cookie.address)
+        .await;
+
+    assert_eq!(chat_message_account.proposal, proposal_cookie.address);
+}
+
+#[tokio::test]
+async fn test_post_message_during_signing_off() {
+    // Arrange
+    //
+    // Two signatories are registered so that signing off as only one of them parks the
+    // Proposal in SigningOff instead of immediately advancing it to Voting (spl-governance
+    // only transitions to Voting once every registered signatory has signed off).
+    let mut governance_chat_test = GovernanceChatProgramTest::start_new().await;
+    let proposal_cookie = governance_chat_test.with_proposal().await;
+
+    let signatory_a_cookie = governance_chat_test
+        .with_token_owner_deposit(&proposal_cookie, 10)
+        .await;
+    let signatory_b_cookie = governance_chat_test
+        .with_token_owner_deposit(&proposal_cookie, 10)
+        .await;
+
+    let signatory_a_record_cookie = governance_chat_test
+        .with_signatory(&proposal_cookie, &signatory_a_cookie)
+        .await;
+    governance_chat_test
+        .with_signatory(&proposal_cookie, &signatory_b_cookie)
+        .await;
+
+    let proposal_cookie = governance_chat_test
+        .sign_off_proposal(proposal_cookie, &signatory_a_cookie.token_owner)
+        .await;
+
+    // Act / Assert - (a) the signed-off signatory can post
+    let signatory_a_token_owner_record_address = signatory_a_cookie.address;
+    let chat_message_cookie = governance_chat_test
+        .with_chat_message_as(
+            &signatory_a_token_owner_record_address,
+            &signatory_a_cookie.token_owner,
+            &proposal_cookie,
+            None,
+            MessageBody::Text("Signatory here".to_string()),
+            Some(signatory_a_record_cookie.address),
+            None,
+        )
+        .await
+        .unwrap();
+
+    let chat_message_account = governance_chat_test
+        .get_message_account(&chat_message_cookie.address)
+        .await;
+    assert_eq!(chat_message_account.proposal, proposal_cookie.address);
+
+    // Act / Assert - (b) a non-signatory is rejected while still SigningOff
+    let non_signatory_cookie = governance_chat_test
+        .with_token_owner_deposit(&proposal_cookie, 10)
+        .await;
+
+    let err = governance_chat_test
+        .with_chat_message_as(
+            &non_signatory_cookie.address,
+            &non_signatory_cookie.token_owner,
+            &proposal_cookie,
+            None,
+            MessageBody::Text("I'm not a signatory".to_string()),
+            None,
+            None,
+        )
+        .await
+        .err()
+        .unwrap();
+
+    assert_eq!(
+        err,
+        GovernanceChatError::InvalidSignatoryRecordForProposal.into()
+    );
+
+    // Act / Assert - (c) once the last signatory signs off the Proposal moves to Voting and
+    // the same non-signatory can post under ordinary token-weight gating
+    let proposal_cookie = governance_chat_test
+        .sign_off_proposal(proposal_cookie, &signatory_b_cookie.token_owner)
+        .await;
+
+    let chat_message_cookie = governance_chat_test
+        .with_chat_message_as(
+            &non_signatory_cookie.address,
+            &non_signatory_cookie.token_owner,
+            &proposal_cookie,
+            None,
+            MessageBody::Text("Now I can comment".to_string()),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+    let chat_message_account = governance_chat_test
+        .get_message_account(&chat_message_cookie.address)
+        .await;
+    assert_eq!(chat_message_account.proposal, proposal_cookie.address);
+}
+
+#[tokio::test]
+async fn test_post_message_to_council_proposal_below_council_min_tokens_errors() {
+    // Arrange
+    let mut governance_chat_test = GovernanceChatProgramTest::start_new().await;
+    let proposal_cookie = governance_chat_test.with_council_proposal().await;
+
+    let council_commenter_cookie = governance_chat_test
+        .with_token_owner_deposit(&proposal_cookie, 1)
+        .await;
+
+    // Act
+    let err = governance_chat_test
+        .with_chat_message_as(
+            &council_commenter_cookie.address,
+            &council_commenter_cookie.token_owner,
+            &proposal_cookie,
+            None,
+            MessageBody::Text("Not enough council tokens".to_string()),
+            None,
+            None,
+        )
+        .await
+        .err()
+        .unwrap();
+
+    // Assert
+    assert_eq!(
+        err,
+        GovernanceChatError::NotEnoughTokensToCommentProposal.into()
+    );
+}
+
+#[tokio::test]
+async fn test_post_message_to_council_proposal_at_council_min_tokens_succeeds() {
+    // Arrange
+    let mut governance_chat_test = GovernanceChatProgramTest::start_new().await;
+    let proposal_cookie = governance_chat_test.with_council_proposal().await;
+
+    let council_commenter_cookie = governance_chat_test
+        .with_token_owner_deposit(&proposal_cookie, 2)
+        .await;
+
+    // Act
+    let chat_message_cookie = governance_chat_test
+        .with_chat_message_as(
+            &council_commenter_cookie.address,
+            &council_commenter_cookie.token_owner,
+            &proposal_cookie,
+            None,
+            MessageBody::Text("Just enough council tokens".to_string()),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+    // Assert
+    let chat_message_account = governance_chat_test
+        .get_message_account(&chat_message_cookie.address)
+        .await;
+    assert_eq!(chat_message_account.proposal, proposal_cookie.address);
+}
+
+#[tokio::test]
+async fn test_post_message_to_council_proposal_below_community_min_tokens_errors() {
+    // Arrange
+    //
+    // A community-mint commenter on a council Proposal is still gated by the Realm's
+    // community minimum, not the council minimum the Proposal itself was created with.
+    let mut governance_chat_test = GovernanceChatProgramTest::start_new().await;
+    let proposal_cookie = governance_chat_test.with_council_proposal().await;
+
+    let community_commenter_cookie = governance_chat_test
+        .with_community_token_owner_deposit(&proposal_cookie, 4)
+        .await;
+
+    // Act
+    let err = governance_chat_test
+        .with_chat_message_as(
+            &community_commenter_cookie.address,
+            &community_commenter_cookie.token_owner,
+            &proposal_cookie,
+            None,
+            MessageBody::Text("Not enough community tokens".to_string()),
+            None,
+            None,
+        )
+        .await
+        .err()
+        .unwrap();
+
+    // Assert
+    assert_eq!(
+        err,
+        GovernanceChatError::NotEnoughTokensToCommentProposal.into()
+    );
+}
+
+#[tokio::test]
+async fn test_post_message_to_council_proposal_at_community_min_tokens_succeeds() {
+    // Arrange
+    let mut governance_chat_test = GovernanceChatProgramTest::start_new().await;
+    let proposal_cookie = governance_chat_test.with_council_proposal().await;
+
+    let community_commenter_cookie = governance_chat_test
+        .with_community_token_owner_deposit(&proposal_cookie, 5)
+        .await;
+
+    // Act
+    let chat_message_cookie = governance_chat_test
+        .with_chat_message_as(
+            &community_commenter_cookie.address,
+            &community_commenter_cookie.token_owner,
+            &proposal_cookie,
+            None,
+            MessageBody::Text("Just enough community tokens".to_string()),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+    // Assert
+    let chat_message_account = governance_chat_test
+        .get_message_account(&chat_message_cookie.address)
+        .await;
+    assert_eq!(chat_message_account.proposal, proposal_cookie.address);
+}
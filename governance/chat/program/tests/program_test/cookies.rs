@@ -0,0 +1,40 @@
+use gemachain_program::pubkey::Pubkey;
+use gemachain_sdk::signature::Keypair;
+use spl_governance_addin_api::voter_weight::VoterWeightRecord;
+use spl_governance_chat::state::ChatMessage;
+
+pub struct ProposalCookie {
+    pub address: Pubkey,
+    pub realm_address: Pubkey,
+    pub governance_address: Pubkey,
+    pub token_owner_record_address: Pubkey,
+    pub token_owner: Keypair,
+    pub governing_token_mint: Pubkey,
+    pub governing_token_mint_authority: Keypair,
+
+    /// The Realm's community mint. Equal to `governing_token_mint` for a Realm without a
+    /// separate council mint, and distinct from it for a council Proposal, so tests can
+    /// deposit the *other* mint on the same Realm and exercise the community/council split.
+    pub community_mint: Pubkey,
+    pub community_mint_authority: Keypair,
+}
+
+pub struct TokenOwnerRecordCookie {
+    pub address: Pubkey,
+    pub token_owner: Keypair,
+}
+
+pub struct ChatMessageCookie {
+    pub address: Pubkey,
+    pub account: ChatMessage,
+}
+
+pub struct VoterWeightRecordCookie {
+    pub address: Pubkey,
+    pub account: VoterWeightRecord,
+}
+
+pub struct SignatoryRecordCookie {
+    pub address: Pubkey,
+    pub signatory: Pubkey,
+}
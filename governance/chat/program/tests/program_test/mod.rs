@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::str::FromStr;
 
 use gemachain_program::{program_error::ProgramError, pubkey::Pubkey};
@@ -6,24 +7,33 @@ use gemachain_program_test::processor;
 use gemachain_sdk::{signature::Keypair, signer::Signer};
 use spl_governance::{
     instruction::{
-        create_account_governance, create_proposal, create_realm, deposit_governing_tokens,
+        add_signatory, cancel_proposal, cast_vote, create_account_governance, create_proposal,
+        create_realm, deposit_governing_tokens, finalize_vote, set_realm_config, sign_off_proposal,
     },
     state::{
         enums::{MintMaxVoteWeightSource, VoteThresholdPercentage},
         governance::{get_account_governance_address, GovernanceConfig},
         proposal::get_proposal_address,
         realm::get_realm_address,
+        signatory_record::get_signatory_record_address,
         token_owner_record::get_token_owner_record_address,
+        vote_record::Vote,
     },
 };
+use spl_governance_addin_api::voter_weight::{VoterWeightAction, VoterWeightRecord};
 use spl_governance_chat::{
     instruction::post_message,
     processor::process_instruction,
-    state::{ChatMessage, GovernanceChatAccountType, MessageBody},
+    state::{
+        get_reaction_summary_address, ChatMessage, GovernanceChatAccountType, MessageBody,
+        ReactionSummary,
+    },
 };
 use spl_governance_test_sdk::{ProgramTestBench, TestBenchProgram};
 
-use crate::program_test::cookies::{ChatMessageCookie, ProposalCookie};
+use crate::program_test::cookies::{
+    ChatMessageCookie, ProposalCookie, SignatoryRecordCookie, VoterWeightRecordCookie,
+};
 
 use self::cookies::TokenOwnerRecordCookie;
 
@@ -33,6 +43,11 @@ pub struct GovernanceChatProgramTest {
     pub bench: ProgramTestBench,
     pub program_id: Pubkey,
     pub governance_program_id: Pubkey,
+
+    /// Realm authorities captured from `with_proposal`, keyed by realm address, so that
+    /// later helpers (e.g. `with_voter_weight_addin`) can sign config updates for a realm
+    /// without the caller having to thread the authority keypair around by hand.
+    pub realm_authorities: HashMap<Pubkey, Keypair>,
 }
 
 impl GovernanceChatProgramTest {
@@ -59,6 +74,7 @@ impl GovernanceChatProgramTest {
             bench,
             program_id,
             governance_program_id,
+            realm_authorities: HashMap::new(),
         }
     }
 
@@ -98,6 +114,9 @@ impl GovernanceChatProgramTest {
             .await
             .unwrap();
 
+        self.realm_authorities
+            .insert(realm_address, clone_keypair(&realm_authority));
+
         // Create TokenOwnerRecord
         let token_owner = Keypair::new();
         let token_source = Keypair::new();
@@ -206,6 +225,8 @@ impl GovernanceChatProgramTest {
             &proposal_index.to_le_bytes(),
         );
 
+        let community_mint_authority = clone_keypair(&governing_token_mint_authority);
+
         ProposalCookie {
             address: proposal_address,
             realm_address,
@@ -213,7 +234,177 @@ impl GovernanceChatProgramTest {
             token_owner_record_address,
             token_owner,
             governing_token_mint: governing_token_mint_keypair.pubkey(),
-            governing_token_mint_authority: governing_token_mint_authority,
+            governing_token_mint_authority,
+            community_mint: governing_token_mint_keypair.pubkey(),
+            community_mint_authority,
+        }
+    }
+
+    /// Like `with_proposal` but creates the Realm with both a community and a council mint and
+    /// deposits council tokens for the proposer, so the returned ProposalCookie's
+    /// `governing_token_mint` is the council mint and chat tests can exercise council-only
+    /// commenting.
+    #[allow(dead_code)]
+    pub async fn with_council_proposal(&mut self) -> ProposalCookie {
+        // Create Realm
+        let name = self.bench.get_unique_name("realm");
+
+        let realm_address = get_realm_address(&self.governance_program_id, &name);
+
+        let community_mint_keypair = Keypair::new();
+        let community_mint_authority = Keypair::new();
+
+        self.bench
+            .create_mint(&community_mint_keypair, &community_mint_authority.pubkey())
+            .await;
+
+        let council_mint_keypair = Keypair::new();
+        let council_mint_authority = Keypair::new();
+
+        self.bench
+            .create_mint(&council_mint_keypair, &council_mint_authority.pubkey())
+            .await;
+
+        let realm_authority = Keypair::new();
+
+        let create_realm_ix = create_realm(
+            &self.governance_program_id,
+            &realm_authority.pubkey(),
+            &community_mint_keypair.pubkey(),
+            &self.bench.payer.pubkey(),
+            Some(council_mint_keypair.pubkey()),
+            None,
+            name.clone(),
+            1,
+            MintMaxVoteWeightSource::FULL_SUPPLY_FRACTION,
+        );
+
+        self.bench
+            .process_transaction(&[create_realm_ix], None)
+            .await
+            .unwrap();
+
+        self.realm_authorities
+            .insert(realm_address, clone_keypair(&realm_authority));
+
+        // Create TokenOwnerRecord funded with council tokens
+        let token_owner = Keypair::new();
+        let token_source = Keypair::new();
+
+        let transfer_authority = Keypair::new();
+        let amount = 100;
+
+        self.bench
+            .create_token_account_with_transfer_authority(
+                &token_source,
+                &council_mint_keypair.pubkey(),
+                &council_mint_authority,
+                amount,
+                &token_owner,
+                &transfer_authority.pubkey(),
+            )
+            .await;
+
+        let deposit_governing_tokens_ix = deposit_governing_tokens(
+            &self.governance_program_id,
+            &realm_address,
+            &token_source.pubkey(),
+            &token_owner.pubkey(),
+            &token_owner.pubkey(),
+            &self.bench.payer.pubkey(),
+            amount,
+            &council_mint_keypair.pubkey(),
+        );
+
+        self.bench
+            .process_transaction(&[deposit_governing_tokens_ix], Some(&[&token_owner]))
+            .await
+            .unwrap();
+
+        // Create Governance
+        let governed_account_address = Pubkey::new_unique();
+
+        let governance_config = GovernanceConfig {
+            min_community_tokens_to_create_proposal: 5,
+            min_council_tokens_to_create_proposal: 2,
+            min_instruction_hold_up_time: 10,
+            max_voting_time: 10,
+            vote_threshold_percentage: VoteThresholdPercentage::YesVote(60),
+            vote_weight_source: spl_governance::state::enums::VoteWeightSource::Deposit,
+            proposal_cool_off_time: 0,
+        };
+
+        let token_owner_record_address = get_token_owner_record_address(
+            &self.governance_program_id,
+            &realm_address,
+            &council_mint_keypair.pubkey(),
+            &token_owner.pubkey(),
+        );
+
+        let create_account_governance_ix = create_account_governance(
+            &self.governance_program_id,
+            &realm_address,
+            &governed_account_address,
+            &token_owner_record_address,
+            &self.bench.payer.pubkey(),
+            &token_owner.pubkey(),
+            None,
+            governance_config,
+        );
+
+        self.bench
+            .process_transaction(&[create_account_governance_ix], Some(&[&token_owner]))
+            .await
+            .unwrap();
+
+        // Create Proposal
+
+        let governance_address = get_account_governance_address(
+            &self.governance_program_id,
+            &realm_address,
+            &governed_account_address,
+        );
+
+        let proposal_name = "Proposal #1".to_string();
+        let description_link = "Proposal Description".to_string();
+        let proposal_index: u32 = 0;
+
+        let create_proposal_ix = create_proposal(
+            &self.governance_program_id,
+            &governance_address,
+            &token_owner_record_address,
+            &token_owner.pubkey(),
+            &self.bench.payer.pubkey(),
+            None,
+            &realm_address,
+            proposal_name,
+            description_link.clone(),
+            &council_mint_keypair.pubkey(),
+            proposal_index,
+        );
+
+        self.bench
+            .process_transaction(&[create_proposal_ix], Some(&[&token_owner]))
+            .await
+            .unwrap();
+
+        let proposal_address = get_proposal_address(
+            &self.governance_program_id,
+            &governance_address,
+            &council_mint_keypair.pubkey(),
+            &proposal_index.to_le_bytes(),
+        );
+
+        ProposalCookie {
+            address: proposal_address,
+            realm_address,
+            governance_address,
+            token_owner_record_address,
+            token_owner,
+            governing_token_mint: council_mint_keypair.pubkey(),
+            governing_token_mint_authority: council_mint_authority,
+            community_mint: community_mint_keypair.pubkey(),
+            community_mint_authority,
         }
     }
 
@@ -267,26 +458,300 @@ impl GovernanceChatProgramTest {
         }
     }
 
+    /// Like `with_token_owner_deposit` but always deposits the Realm's community mint, even
+    /// when `proposal_cookie.governing_token_mint` is the council mint. Lets tests check that a
+    /// community-mint TokenOwnerRecord is gated by the community (not council) minimum when
+    /// commenting on a council Proposal.
+    #[allow(dead_code)]
+    pub async fn with_community_token_owner_deposit(
+        &mut self,
+        proposal_cookie: &ProposalCookie,
+        deposit_amount: u64,
+    ) -> TokenOwnerRecordCookie {
+        let token_owner = Keypair::new();
+        let token_source = Keypair::new();
+
+        let transfer_authority = Keypair::new();
+
+        self.bench
+            .create_token_account_with_transfer_authority(
+                &token_source,
+                &proposal_cookie.community_mint,
+                &proposal_cookie.community_mint_authority,
+                deposit_amount,
+                &token_owner,
+                &transfer_authority.pubkey(),
+            )
+            .await;
+
+        let deposit_governing_tokens_ix = deposit_governing_tokens(
+            &self.governance_program_id,
+            &proposal_cookie.realm_address,
+            &token_source.pubkey(),
+            &token_owner.pubkey(),
+            &token_owner.pubkey(),
+            &self.bench.payer.pubkey(),
+            deposit_amount,
+            &proposal_cookie.community_mint,
+        );
+
+        self.bench
+            .process_transaction(&[deposit_governing_tokens_ix], Some(&[&token_owner]))
+            .await
+            .unwrap();
+
+        let token_owner_record_address = get_token_owner_record_address(
+            &self.governance_program_id,
+            &proposal_cookie.realm_address,
+            &proposal_cookie.community_mint,
+            &token_owner.pubkey(),
+        );
+        TokenOwnerRecordCookie {
+            address: token_owner_record_address,
+            token_owner,
+        }
+    }
+
+    /// Signs off the Proposal as `signatory`. When the Proposal has no signatories, the owner
+    /// signs off directly and the Proposal moves straight to Voting; when it does, this moves
+    /// the Proposal to SigningOff on the first sign-off and on to Voting only once every
+    /// signatory has signed off.
+    #[allow(dead_code)]
+    pub async fn sign_off_proposal(
+        &mut self,
+        proposal_cookie: ProposalCookie,
+        signatory: &Keypair,
+    ) -> ProposalCookie {
+        let sign_off_proposal_ix = sign_off_proposal(
+            &self.governance_program_id,
+            &proposal_cookie.governance_address,
+            &proposal_cookie.address,
+            &signatory.pubkey(),
+        );
+
+        self.bench
+            .process_transaction(&[sign_off_proposal_ix], Some(&[signatory]))
+            .await
+            .unwrap();
+
+        proposal_cookie
+    }
+
+    #[allow(dead_code)]
+    pub async fn cast_yes_no_vote(
+        &mut self,
+        proposal_cookie: ProposalCookie,
+        yes_no_vote: bool,
+    ) -> ProposalCookie {
+        let vote = if yes_no_vote {
+            Vote::Approve(vec![])
+        } else {
+            Vote::Deny
+        };
+
+        let cast_vote_ix = cast_vote(
+            &self.governance_program_id,
+            &proposal_cookie.realm_address,
+            &proposal_cookie.governance_address,
+            &proposal_cookie.address,
+            &proposal_cookie.token_owner_record_address,
+            &proposal_cookie.token_owner_record_address,
+            &proposal_cookie.token_owner.pubkey(),
+            &proposal_cookie.governing_token_mint,
+            &self.bench.payer.pubkey(),
+            None,
+            vote,
+        );
+
+        self.bench
+            .process_transaction(&[cast_vote_ix], Some(&[&proposal_cookie.token_owner]))
+            .await
+            .unwrap();
+
+        proposal_cookie
+    }
+
+    #[allow(dead_code)]
+    pub async fn finalize_vote(&mut self, proposal_cookie: ProposalCookie) -> ProposalCookie {
+        let finalize_vote_ix = finalize_vote(
+            &self.governance_program_id,
+            &proposal_cookie.realm_address,
+            &proposal_cookie.governance_address,
+            &proposal_cookie.address,
+            &proposal_cookie.token_owner_record_address,
+            &proposal_cookie.governing_token_mint,
+        );
+
+        self.bench
+            .process_transaction(&[finalize_vote_ix], None)
+            .await
+            .unwrap();
+
+        proposal_cookie
+    }
+
+    #[allow(dead_code)]
+    pub async fn cancel_proposal(&mut self, proposal_cookie: ProposalCookie) -> ProposalCookie {
+        let cancel_proposal_ix = cancel_proposal(
+            &self.governance_program_id,
+            &proposal_cookie.governance_address,
+            &proposal_cookie.address,
+            &proposal_cookie.token_owner_record_address,
+            &proposal_cookie.token_owner.pubkey(),
+        );
+
+        self.bench
+            .process_transaction(&[cancel_proposal_ix], Some(&[&proposal_cookie.token_owner]))
+            .await
+            .unwrap();
+
+        proposal_cookie
+    }
+
+    /// Adds `token_owner_record_cookie`'s owner as a required signatory of the Proposal,
+    /// mirroring spl-governance's `add_signatory`. While the Proposal is SigningOff, only
+    /// its signatories may post chat messages.
+    #[allow(dead_code)]
+    pub async fn with_signatory(
+        &mut self,
+        proposal_cookie: &ProposalCookie,
+        token_owner_record_cookie: &TokenOwnerRecordCookie,
+    ) -> SignatoryRecordCookie {
+        let signatory = token_owner_record_cookie.token_owner.pubkey();
+
+        let add_signatory_ix = add_signatory(
+            &self.governance_program_id,
+            &proposal_cookie.address,
+            &proposal_cookie.token_owner_record_address,
+            &proposal_cookie.token_owner.pubkey(),
+            &self.bench.payer.pubkey(),
+            signatory,
+        );
+
+        self.bench
+            .process_transaction(&[add_signatory_ix], Some(&[&proposal_cookie.token_owner]))
+            .await
+            .unwrap();
+
+        let signatory_record_address = get_signatory_record_address(
+            &self.governance_program_id,
+            &proposal_cookie.address,
+            &signatory,
+        );
+
+        SignatoryRecordCookie {
+            address: signatory_record_address,
+            signatory,
+        }
+    }
+
+    #[allow(dead_code)]
+    pub async fn with_voter_weight_addin(
+        &mut self,
+        realm_address: &Pubkey,
+        plugin_program_id: &Pubkey,
+    ) {
+        let realm_authority = self
+            .realm_authorities
+            .get(realm_address)
+            .expect("realm authority not tracked for realm_address");
+
+        let set_realm_config_ix = set_realm_config(
+            &self.governance_program_id,
+            realm_address,
+            &realm_authority.pubkey(),
+            None,
+            None,
+            None,
+            Some(*plugin_program_id),
+            None,
+        );
+
+        self.bench
+            .process_transaction(&[set_realm_config_ix], Some(&[realm_authority]))
+            .await
+            .unwrap();
+    }
+
+    #[allow(dead_code)]
+    pub async fn with_voter_weight_record(
+        &mut self,
+        proposal_cookie: &ProposalCookie,
+        governing_token_owner: &Pubkey,
+        voter_weight: u64,
+    ) -> VoterWeightRecordCookie {
+        let voter_weight_record_keypair = Keypair::new();
+
+        let voter_weight_record = VoterWeightRecord {
+            realm: proposal_cookie.realm_address,
+            governing_token_mint: proposal_cookie.governing_token_mint,
+            governing_token_owner: *governing_token_owner,
+            voter_weight,
+            voter_weight_expiry: None,
+            weight_action: Some(VoterWeightAction::CommentProposal),
+            weight_action_target: Some(proposal_cookie.address),
+        };
+
+        self.bench
+            .create_account_with_data(&voter_weight_record_keypair, &voter_weight_record)
+            .await;
+
+        VoterWeightRecordCookie {
+            address: voter_weight_record_keypair.pubkey(),
+            account: voter_weight_record,
+        }
+    }
+
     #[allow(dead_code)]
     pub async fn with_chat_message(
         &mut self,
         proposal_cookie: &ProposalCookie,
         reply_to: Option<Pubkey>,
+        message_body: MessageBody,
+        signatory_record: Option<Pubkey>,
+        voter_weight_record: Option<Pubkey>,
+    ) -> Result<ChatMessageCookie, ProgramError> {
+        self.with_chat_message_as(
+            &proposal_cookie.token_owner_record_address,
+            &clone_keypair(&proposal_cookie.token_owner),
+            proposal_cookie,
+            reply_to,
+            message_body,
+            signatory_record,
+            voter_weight_record,
+        )
+        .await
+    }
+
+    /// Like `with_chat_message` but posts on behalf of an arbitrary TokenOwnerRecord/owner
+    /// instead of the Proposal's own owner, so tests can exercise commenting by a signatory,
+    /// a different council/community token owner, etc.
+    #[allow(dead_code)]
+    pub async fn with_chat_message_as(
+        &mut self,
+        token_owner_record_address: &Pubkey,
+        token_owner: &Keypair,
+        proposal_cookie: &ProposalCookie,
+        reply_to: Option<Pubkey>,
+        message_body: MessageBody,
+        signatory_record: Option<Pubkey>,
+        voter_weight_record: Option<Pubkey>,
     ) -> Result<ChatMessageCookie, ProgramError> {
         let message_account = Keypair::new();
-        let message_body = MessageBody::Text("My comment".to_string());
 
         let post_message_ix = post_message(
             &self.program_id,
             &self.governance_program_id,
             &proposal_cookie.governance_address,
             &proposal_cookie.address,
-            &proposal_cookie.token_owner_record_address,
-            &proposal_cookie.token_owner.pubkey(),
+            token_owner_record_address,
+            &token_owner.pubkey(),
             reply_to,
             &message_account.pubkey(),
             &self.bench.payer.pubkey(),
             message_body.clone(),
+            signatory_record,
+            voter_weight_record,
         );
 
         let clock = self.bench.get_clock().await;
@@ -294,17 +759,14 @@ impl GovernanceChatProgramTest {
         let message = ChatMessage {
             account_type: GovernanceChatAccountType::ChatMessage,
             proposal: proposal_cookie.address,
-            author: proposal_cookie.token_owner.pubkey(),
+            author: token_owner.pubkey(),
             posted_at: clock.unix_timestamp,
             reply_to,
             body: message_body,
         };
 
         self.bench
-            .process_transaction(
-                &[post_message_ix],
-                Some(&[&proposal_cookie.token_owner, &message_account]),
-            )
+            .process_transaction(&[post_message_ix], Some(&[token_owner, &message_account]))
             .await?;
 
         Ok(ChatMessageCookie {
@@ -319,4 +781,18 @@ impl GovernanceChatProgramTest {
             .get_borsh_account::<ChatMessage>(message_address)
             .await
     }
+
+    #[allow(dead_code)]
+    pub async fn get_reaction_summary(&mut self, message_address: &Pubkey) -> ReactionSummary {
+        let reaction_summary_address =
+            get_reaction_summary_address(&self.program_id, message_address);
+
+        self.bench
+            .get_borsh_account::<ReactionSummary>(&reaction_summary_address)
+            .await
+    }
+}
+
+pub(crate) fn clone_keypair(keypair: &Keypair) -> Keypair {
+    Keypair::from_bytes(&keypair.to_bytes()).unwrap()
 }